@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+/// Structured error type surfaced to callers across the core crate.
+/// Specific failure modes get their own variant so callers can match on
+/// the cause; anything without a dedicated variant yet falls back to
+/// `Other`, carrying full `anyhow` context.
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("failed to open repository with gitoxide")]
+    GixOpen(#[source] Box<gix::open::Error>),
+    #[error("failed to initialize reference iterator")]
+    GixReferenceIterInit(#[source] gix::reference::iter::init::Error),
+    #[error("failed to iterate references")]
+    GixReferenceIter(#[source] gix::reference::iter::Error),
+    #[error("failed to read repository head with gitoxide")]
+    GixHeadRead(#[source] Box<gix::reference::find::existing::Error>),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
@@ -13,6 +13,12 @@ use gitbutler_core::{
 };
 
 use crate::error::Error;
+use crate::fuzzy;
+
+/// Config key that opts a project into the gitoxide fast path for
+/// read-only reference queries. Defaults to off so libgit2 remains the
+/// default until the fast path has had more mileage.
+const GIX_FASTPATH_CONFIG_KEY: &str = "gitbutler.gixFastpath";
 
 #[derive(Clone)]
 pub struct App {
@@ -68,6 +74,79 @@ impl App {
             .context("failed to read session files")?)
     }
 
+    /// Fuzzy-searches the paths of a session's files against `query`,
+    /// ranking them by match quality instead of handing callers the full
+    /// `list_session_files` map to filter client-side. Content is only
+    /// read for paths that actually matched, keeping this cheap for an
+    /// interactive file picker.
+    ///
+    /// An empty `query` returns every path, unranked.
+    pub fn search_session_files(
+        &self,
+        project_id: &ProjectId,
+        session_id: &SessionId,
+        query: &str,
+    ) -> Result<Vec<(path::PathBuf, reader::Content)>, Error> {
+        let session = self
+            .sessions_database
+            .get_by_project_id_id(project_id, session_id)
+            .context("failed to get session")?
+            .context("session not found")?;
+        let user = self.users.get_user().context("failed to get user")?;
+        let project = self
+            .projects
+            .get(project_id)
+            .map_err(Error::from_error_with_context)?;
+        let project_repository = project_repository::Repository::open(&project)
+            .map_err(Error::from_error_with_context)?;
+        let gb_repo = gb_repository::Repository::open(
+            &self.local_data_dir,
+            &project_repository,
+            user.as_ref(),
+        )
+        .context("failed to open gb repository")?;
+        let session_reader =
+            sessions::Reader::open(&gb_repo, &session).context("failed to open session reader")?;
+
+        let paths = session_reader
+            .list_files(path::Path::new(""))
+            .context("failed to list session files")?;
+
+        let matched_paths: Vec<path::PathBuf> = if query.is_empty() {
+            paths
+        } else {
+            let mut scored: Vec<(i64, path::PathBuf)> = paths
+                .into_iter()
+                .filter_map(|path| {
+                    let score = fuzzy::score(query, &path.to_string_lossy())?;
+                    (score > 0).then_some((score, path))
+                })
+                .collect();
+            scored.sort_by(|(score_a, path_a), (score_b, path_b)| {
+                score_b
+                    .cmp(score_a)
+                    .then_with(|| path_a.as_os_str().len().cmp(&path_b.as_os_str().len()))
+            });
+            scored.into_iter().map(|(_, path)| path).collect()
+        };
+
+        if matched_paths.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Only the paths that actually matched are read here; everything
+        // else in the session never has its `Content` loaded.
+        let path_refs: Vec<&path::Path> = matched_paths.iter().map(path::PathBuf::as_path).collect();
+        let mut contents = session_reader
+            .files(Some(&path_refs))
+            .context("failed to read session files")?;
+
+        Ok(matched_paths
+            .into_iter()
+            .filter_map(|path| contents.remove(&path).map(|content| (path, content)))
+            .collect())
+    }
+
     pub fn mark_resolved(&self, project_id: &ProjectId, path: &str) -> Result<(), CoreError> {
         let project = self.projects.get(project_id)?;
         let project_repository = project_repository::Repository::open(&project)?;
@@ -81,10 +160,46 @@ impl App {
         project_id: &ProjectId,
     ) -> Result<Vec<git::RemoteRefname>, CoreError> {
         let project = self.projects.get(project_id)?;
+        if Self::gix_fastpath_enabled(&project) {
+            return Self::git_remote_branches_gix(&project);
+        }
         let project_repository = project_repository::Repository::open(&project)?;
         Ok(project_repository.git_remote_branches()?)
     }
 
+    /// Enumerates `refs/remotes/*` via gitoxide instead of opening a full
+    /// libgit2 repository, skipping the index load that
+    /// `project_repository::Repository::open` otherwise pays for.
+    fn git_remote_branches_gix(
+        project: &projects::Project,
+    ) -> Result<Vec<git::RemoteRefname>, CoreError> {
+        let repo = gix::open(&project.path).map_err(|e| CoreError::GixOpen(Box::new(e)))?;
+        let references = repo
+            .references()
+            .map_err(CoreError::GixReferenceIterInit)?;
+        let mut remote_branches = Vec::new();
+        for reference in references.all().map_err(CoreError::GixReferenceIterInit)? {
+            let reference = reference.map_err(CoreError::GixReferenceIter)?;
+            let name = reference.name().as_bstr().to_string();
+            if let Some(rest) = name.strip_prefix("refs/remotes/") {
+                if let Some((remote, branch)) = rest.split_once('/') {
+                    remote_branches.push(git::RemoteRefname::new(remote, branch));
+                }
+            }
+        }
+        Ok(remote_branches)
+    }
+
+    /// Whether `project` has opted into the gitoxide fast path for
+    /// read-only reference queries, via `git config gitbutler.gixFastpath`.
+    /// Libgit2 remains the default so this can roll out gradually.
+    fn gix_fastpath_enabled(project: &projects::Project) -> bool {
+        gix::open(&project.path)
+            .ok()
+            .and_then(|repo| repo.config_snapshot().boolean(GIX_FASTPATH_CONFIG_KEY))
+            .unwrap_or(false)
+    }
+
     pub fn git_test_push(
         &self,
         project_id: &ProjectId,
@@ -121,6 +236,9 @@ impl App {
 
     pub fn git_head(&self, project_id: &ProjectId) -> Result<String, CoreError> {
         let project = self.projects.get(project_id)?;
+        if Self::gix_fastpath_enabled(&project) {
+            return Self::git_head_gix(&project);
+        }
         let project_repository = project_repository::Repository::open(&project)?;
         let head = project_repository
             .get_head()
@@ -128,6 +246,18 @@ impl App {
         Ok(head.name().unwrap().to_string())
     }
 
+    /// Reads the name of `HEAD` via gitoxide without opening a full
+    /// libgit2 repository.
+    fn git_head_gix(project: &projects::Project) -> Result<String, CoreError> {
+        let repo = gix::open(&project.path).map_err(|e| CoreError::GixOpen(Box::new(e)))?;
+        let head_name = repo
+            .head_name()
+            .map_err(|e| CoreError::GixHeadRead(Box::new(e)))?
+            .map(|name| name.as_bstr().to_string())
+            .unwrap_or_else(|| "HEAD".to_string());
+        Ok(head_name)
+    }
+
     pub fn git_set_global_config(key: &str, value: &str) -> Result<String> {
         let mut config = git2::Config::open_default()?;
         config.set_str(key, value)?;
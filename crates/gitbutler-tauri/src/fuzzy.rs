@@ -0,0 +1,181 @@
+//! A small subsequence fuzzy matcher for ranking file paths against a
+//! search query, used by [`crate::app::App::search_session_files`].
+
+/// Bonus for a match that starts right after a path separator or
+/// word-boundary character.
+const BOUNDARY_BONUS: i64 = 10;
+/// Bonus for a match landing on a camelCase hump (preceding char is
+/// lowercase, this one is uppercase).
+const CAMEL_BONUS: i64 = 8;
+/// Bonus for two matched characters in a row.
+const CONSECUTIVE_BONUS: i64 = 12;
+/// Penalty charged per unmatched character between consecutive matches.
+const GAP_PENALTY: i64 = 2;
+/// Penalty charged per unmatched character before the first match.
+const LEADING_PENALTY: i64 = 1;
+/// How many characters from the start of the path still earn a small
+/// position bonus; matches further in don't get any.
+const START_PROXIMITY_WINDOW: i64 = 16;
+
+/// Scores `candidate` against `query` as a fuzzy subsequence match.
+///
+/// Returns `None` if `query` doesn't appear in `candidate`, case
+/// insensitively, as an in-order subsequence. Otherwise returns a score
+/// where higher is a better match: consecutive runs, matches right after a
+/// separator (`/`, `_`, `-`, `.`) or a camelCase boundary, and matches
+/// near the start of the path are all rewarded, while gaps between
+/// matches and unmatched leading characters are penalized.
+///
+/// An empty `query` always scores `0` (every path "matches", unranked).
+/// Callers that only want positive-scoring candidates filter the result
+/// themselves; this function doesn't apply that threshold.
+pub fn score(query: &str, candidate: &str) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+
+    // `char::to_lowercase` can expand a single character into several
+    // (e.g. Turkish `İ` → `i̇`), which would desynchronize `*_lower` from
+    // `query`/`candidate` and corrupt every index used below to look back
+    // into the original strings. Take just the first lowered char instead
+    // so each position stays 1:1 with its original string.
+    let lower_char = |c: &char| c.to_lowercase().next().unwrap_or(*c);
+
+    let query: Vec<char> = query.chars().collect();
+    let query_lower: Vec<char> = query.iter().map(lower_char).collect();
+    let candidate: Vec<char> = candidate.chars().collect();
+    let candidate_lower: Vec<char> = candidate.iter().map(lower_char).collect();
+
+    let n = candidate.len();
+    let m = query_lower.len();
+    if m > n {
+        return None;
+    }
+
+    let bonus_at = |j: usize| -> i64 {
+        let boundary = if j == 0 {
+            true
+        } else {
+            matches!(candidate[j - 1], '/' | '_' | '-' | '.')
+        };
+        let camel = j > 0 && candidate[j - 1].is_lowercase() && candidate[j].is_uppercase();
+        let position = (START_PROXIMITY_WINDOW - j as i64).max(0);
+        position + if boundary {
+            BOUNDARY_BONUS
+        } else if camel {
+            CAMEL_BONUS
+        } else {
+            0
+        }
+    };
+
+    const UNREACHABLE: i64 = i64::MIN / 2;
+    // dp[i][j] = best score matching query[..=i] with query[i] landing on
+    // candidate[j]; -infinity if that's not achievable.
+    let mut dp = vec![vec![UNREACHABLE; n]; m];
+
+    for j in 0..n {
+        if candidate_lower[j] != query_lower[0] {
+            continue;
+        }
+        dp[0][j] = bonus_at(j) - LEADING_PENALTY * j as i64;
+    }
+
+    for i in 1..m {
+        for j in i..n {
+            if candidate_lower[j] != query_lower[i] {
+                continue;
+            }
+            let mut best = UNREACHABLE;
+            for k in (i - 1)..j {
+                if dp[i - 1][k] <= UNREACHABLE {
+                    continue;
+                }
+                let gap = j - k - 1;
+                let transition = if gap == 0 {
+                    CONSECUTIVE_BONUS
+                } else {
+                    -GAP_PENALTY * gap as i64
+                };
+                let candidate_score = dp[i - 1][k] + transition + bonus_at(j);
+                if candidate_score > best {
+                    best = candidate_score;
+                }
+            }
+            dp[i][j] = best;
+        }
+    }
+
+    dp[m - 1]
+        .iter()
+        .copied()
+        .filter(|score| *score > UNREACHABLE)
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_query_scores_zero() {
+        assert_eq!(score("", "src/app.rs"), Some(0));
+    }
+
+    #[test]
+    fn non_subsequence_does_not_match() {
+        assert_eq!(score("zzz", "src/app.rs"), None);
+    }
+
+    #[test]
+    fn out_of_order_does_not_match() {
+        assert_eq!(score("pas", "app.rs"), None);
+    }
+
+    #[test]
+    fn matches_are_case_insensitive() {
+        assert!(score("APP", "src/app.rs").is_some());
+    }
+
+    #[test]
+    fn consecutive_match_outscores_scattered_match() {
+        let consecutive = score("app", "src/app.rs").unwrap();
+        let scattered = score("app", "a.p.p.rs").unwrap();
+        assert!(consecutive > scattered);
+    }
+
+    #[test]
+    fn boundary_match_outscores_mid_word_match() {
+        // "app" right after a separator in the first candidate, buried
+        // mid-word in the second.
+        let boundary = score("app", "src/app.rs").unwrap();
+        let mid_word = score("app", "wrapper.rs").unwrap();
+        assert!(boundary > mid_word);
+    }
+
+    #[test]
+    fn camel_case_boundary_is_rewarded() {
+        // "ba" lands its first char on the camelCase hump in "FooBar";
+        // "ar" matches the same run of letters shifted by one, missing it.
+        let camel = score("ba", "FooBar").unwrap();
+        let no_boundary = score("ar", "FooBar").unwrap();
+        assert!(camel > no_boundary);
+    }
+
+    #[test]
+    fn earlier_match_outscores_later_match_of_same_quality() {
+        let early = score("main", "main.rs").unwrap();
+        let late = score("main", "src/handlers/main.rs").unwrap();
+        assert!(early > late);
+    }
+
+    #[test]
+    fn does_not_panic_on_unicode_case_expansion() {
+        // Turkish dotted capital I lowercases to a two-codepoint sequence
+        // ("i" + combining dot above); this must not panic or desync
+        // indices when scoring paths containing it.
+        assert!(score("i", "İ").is_some());
+        assert!(score("dosya", "İ/dosya.rs").is_some());
+        assert_eq!(score("zzz", "İ/dosya.rs"), None);
+    }
+}
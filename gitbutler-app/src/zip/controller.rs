@@ -2,6 +2,7 @@ use std::path;
 
 use crate::projects::{self, ProjectId};
 
+use super::format::{self, ArchiveFormat, ArchiveFormatError};
 use super::Zipper;
 
 #[derive(Clone)]
@@ -12,6 +13,19 @@ pub struct Controller {
     projects_controller: projects::Controller,
 }
 
+/// The result of one of the `Controller` archive methods: where the
+/// archive landed, and a label for the format it was written in, so the
+/// UI can describe a shared bundle (e.g. "encrypted") correctly.
+///
+/// Holds [`ArchiveFormat::label`] rather than the `ArchiveFormat` itself
+/// (and doesn't derive `Debug`), since `Encrypted` carries a plaintext
+/// passphrase that shouldn't outlive the call that wrote the archive.
+#[derive(Clone)]
+pub struct Archive {
+    pub path: path::PathBuf,
+    pub format: &'static str,
+}
+
 impl Controller {
     pub fn new(
         local_data_dir: path::PathBuf,
@@ -27,24 +41,83 @@ impl Controller {
         }
     }
 
-    pub fn archive(&self, project_id: &ProjectId) -> Result<path::PathBuf, ArchiveError> {
+    pub fn archive(
+        &self,
+        project_id: &ProjectId,
+        format: ArchiveFormat,
+    ) -> Result<Archive, ArchiveError> {
         let project = self.projects_controller.get(project_id)?;
-        self.zipper.zip(project.path).map_err(Into::into)
+        let label = format.label();
+        let path = self.write(&project.path, &format)?;
+        Ok(Archive { path, format: label })
     }
 
-    pub fn data_archive(&self, project_id: &ProjectId) -> Result<path::PathBuf, DataArchiveError> {
+    pub fn data_archive(
+        &self,
+        project_id: &ProjectId,
+        format: ArchiveFormat,
+    ) -> Result<Archive, DataArchiveError> {
         let project = self.projects_controller.get(project_id)?;
-        self.zipper
-            .zip(
-                self.local_data_dir
-                    .join("projects")
-                    .join(project.id.to_string()),
-            )
-            .map_err(Into::into)
+        let dir = self
+            .local_data_dir
+            .join("projects")
+            .join(project.id.to_string());
+        let label = format.label();
+        let path = self.write(&dir, &format)?;
+        Ok(Archive { path, format: label })
     }
 
-    pub fn logs_archive(&self) -> Result<path::PathBuf, LogsArchiveError> {
-        self.zipper.zip(&self.logs_dir).map_err(Into::into)
+    pub fn logs_archive(&self, format: ArchiveFormat) -> Result<Archive, LogsArchiveError> {
+        let label = format.label();
+        let path = self.write(&self.logs_dir, &format)?;
+        Ok(Archive { path, format: label })
+    }
+
+    /// Decrypts an [`Archive`] produced with [`ArchiveFormat::Encrypted`]
+    /// in place, so the UI can offer to open a bundle it just created (or
+    /// one handed back to it) once the user supplies the passphrase.
+    pub fn decrypt_archive(
+        &self,
+        path: &path::Path,
+        passphrase: &str,
+    ) -> Result<(), ArchiveFormatError> {
+        format::decrypt_in_place(path, passphrase)
+    }
+
+    /// Produces an archive of `dir` according to `format`: the existing
+    /// buffered [`Zipper`] for [`ArchiveFormat::Zip`], a streaming zip
+    /// writer for [`ArchiveFormat::StreamingZip`], and a recursive call
+    /// plus in-place encryption for [`ArchiveFormat::Encrypted`].
+    fn write(&self, dir: &path::Path, format: &ArchiveFormat) -> Result<path::PathBuf, ArchiveFormatError> {
+        match format {
+            ArchiveFormat::Zip => Ok(self.zipper.zip(dir)?),
+            ArchiveFormat::StreamingZip => {
+                let name = dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "archive".to_string());
+                // A named, securely-created temp file (unique, non-predictable
+                // name, opened with the usual O_EXCL-style guarantees) rather
+                // than a path we compose ourselves: a guessable path in a
+                // shared temp directory is a symlink-attack target and two
+                // archives sharing a directory basename would otherwise
+                // clobber each other.
+                let tempfile = tempfile::Builder::new()
+                    .prefix(&format!("gitbutler-{name}-"))
+                    .suffix(".zip")
+                    .tempfile()
+                    .map_err(ArchiveFormatError::Io)?;
+                let handle = tempfile.reopen().map_err(ArchiveFormatError::Io)?;
+                format::stream_zip_dir(dir, handle)?;
+                let (_file, output) = tempfile.keep().map_err(|e| ArchiveFormatError::Io(e.error))?;
+                Ok(output)
+            }
+            ArchiveFormat::Encrypted { passphrase, inner } => {
+                let path = self.write(dir, inner)?;
+                format::encrypt_in_place(&path, passphrase)?;
+                Ok(path)
+            }
+        }
     }
 }
 
@@ -53,6 +126,8 @@ pub enum ArchiveError {
     #[error(transparent)]
     GetProject(#[from] projects::GetError),
     #[error(transparent)]
+    Format(#[from] ArchiveFormatError),
+    #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
@@ -61,11 +136,15 @@ pub enum DataArchiveError {
     #[error(transparent)]
     GetProject(#[from] projects::GetError),
     #[error(transparent)]
+    Format(#[from] ArchiveFormatError),
+    #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
 
 #[derive(thiserror::Error, Debug)]
 pub enum LogsArchiveError {
+    #[error(transparent)]
+    Format(#[from] ArchiveFormatError),
     #[error(transparent)]
     Other(#[from] anyhow::Error),
 }
@@ -0,0 +1,192 @@
+use std::{fs, fs::File, io, path::Path};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use argon2::Argon2;
+use rand::RngCore;
+use zip::{write::FileOptions, ZipWriter};
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+
+/// The container a [`super::controller::Controller`] archive method
+/// produces, and how (if at all) its contents are protected.
+///
+/// Deliberately doesn't derive `Debug`: `Encrypted` carries a plaintext
+/// passphrase, and a `Debug` impl is exactly the kind of thing that ends
+/// up in a stray `log::debug!`/error-context call. Callers that just need
+/// to know how an archive was produced should keep [`Self::label`]
+/// around instead of the format itself.
+#[derive(Clone, Default)]
+pub enum ArchiveFormat {
+    /// A plain zip written via the existing [`super::Zipper`]. Default,
+    /// kept for backwards compatibility with existing callers.
+    #[default]
+    Zip,
+    /// A zip written entry-by-entry straight to the destination file
+    /// instead of buffering a full temp copy first, for project data
+    /// directories too large to comfortably duplicate on disk.
+    StreamingZip,
+    /// A zip, produced either of the above ways, then encrypted in place
+    /// with a passphrase-derived key so a shared bundle isn't readable
+    /// without it.
+    Encrypted {
+        passphrase: String,
+        inner: Box<ArchiveFormat>,
+    },
+}
+
+impl ArchiveFormat {
+    /// A short label the UI can use to tell a plain bundle apart from an
+    /// encrypted one when listing archives it created.
+    pub fn label(&self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::StreamingZip => "zip (streamed)",
+            ArchiveFormat::Encrypted { .. } => "zip (encrypted)",
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ArchiveFormatError {
+    #[error("failed to derive encryption key from passphrase")]
+    KeyDerivation,
+    #[error("failed to encrypt or decrypt archive")]
+    Encryption,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Zip(#[from] zip::result::ZipError),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+/// Writes every file under `dir` into a zip, streaming each entry straight
+/// into `output` rather than assembling an intermediate copy of `dir`
+/// first. `output` is a caller-provided, already-opened file so callers
+/// control how (and how securely) the destination is created.
+pub fn stream_zip_dir(dir: &Path, output: File) -> Result<(), ArchiveFormatError> {
+    let mut writer = ZipWriter::new(output);
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    for entry in walkdir::WalkDir::new(dir).into_iter().filter_map(Result::ok) {
+        let path = entry.path();
+        let name = path.strip_prefix(dir).unwrap_or(path).to_string_lossy();
+        if entry.file_type().is_dir() {
+            if !name.is_empty() {
+                writer.add_directory(name, options)?;
+            }
+        } else {
+            writer.start_file(name, options)?;
+            let mut source = File::open(path)?;
+            io::copy(&mut source, &mut writer)?;
+        }
+    }
+
+    writer.finish()?;
+    Ok(())
+}
+
+/// Encrypts the file at `path` in place with a key derived from
+/// `passphrase`, replacing its contents with `salt || nonce || ciphertext`.
+///
+/// Key derivation is Argon2 (so brute-forcing a weak passphrase is slow),
+/// and encryption is AES-256-GCM (so a tampered or truncated archive
+/// fails to decrypt instead of silently yielding garbage).
+pub fn encrypt_in_place(path: &Path, passphrase: &str) -> Result<(), ArchiveFormatError> {
+    let plaintext = fs::read(path)?;
+
+    let mut salt = [0u8; SALT_LEN];
+    rand::thread_rng().fill_bytes(&mut salt);
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+        .map_err(|_| ArchiveFormatError::KeyDerivation)?;
+
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    rand::thread_rng().fill_bytes(&mut nonce_bytes);
+    let cipher =
+        Aes256Gcm::new_from_slice(&key).map_err(|_| ArchiveFormatError::KeyDerivation)?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), plaintext.as_ref())
+        .map_err(|_| ArchiveFormatError::Encryption)?;
+
+    let mut out = Vec::with_capacity(SALT_LEN + NONCE_LEN + ciphertext.len());
+    out.extend_from_slice(&salt);
+    out.extend_from_slice(&nonce_bytes);
+    out.extend_from_slice(&ciphertext);
+    fs::write(path, out)?;
+
+    Ok(())
+}
+
+/// Reverses [`encrypt_in_place`]: reads the `salt || nonce || ciphertext`
+/// layout back out of `path`, re-derives the key from `passphrase`, and
+/// replaces `path`'s contents with the decrypted plaintext. Fails (rather
+/// than returning garbage) if the passphrase is wrong or the file was
+/// truncated or tampered with, since AES-GCM authenticates the ciphertext.
+pub fn decrypt_in_place(path: &Path, passphrase: &str) -> Result<(), ArchiveFormatError> {
+    let data = fs::read(path)?;
+    if data.len() < SALT_LEN + NONCE_LEN {
+        return Err(ArchiveFormatError::Encryption);
+    }
+    let (salt, rest) = data.split_at(SALT_LEN);
+    let (nonce_bytes, ciphertext) = rest.split_at(NONCE_LEN);
+
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|_| ArchiveFormatError::KeyDerivation)?;
+
+    let cipher =
+        Aes256Gcm::new_from_slice(&key).map_err(|_| ArchiveFormatError::KeyDerivation)?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|_| ArchiveFormatError::Encryption)?;
+
+    fs::write(path, plaintext)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encrypt_then_decrypt_round_trips() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        let original = b"not a real zip, just some bytes to protect";
+        std::io::Write::write_all(&mut file, original).unwrap();
+
+        encrypt_in_place(file.path(), "correct horse battery staple").unwrap();
+        let encrypted = fs::read(file.path()).unwrap();
+        assert_ne!(encrypted, original);
+        assert!(encrypted.len() > original.len());
+
+        decrypt_in_place(file.path(), "correct horse battery staple").unwrap();
+        let decrypted = fs::read(file.path()).unwrap();
+        assert_eq!(decrypted, original);
+    }
+
+    #[test]
+    fn decrypt_with_wrong_passphrase_fails() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, b"secret project data").unwrap();
+
+        encrypt_in_place(file.path(), "right passphrase").unwrap();
+        let result = decrypt_in_place(file.path(), "wrong passphrase");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn decrypt_rejects_truncated_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, b"too short").unwrap();
+
+        let result = decrypt_in_place(file.path(), "whatever");
+        assert!(result.is_err());
+    }
+}
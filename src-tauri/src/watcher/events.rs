@@ -0,0 +1,35 @@
+use std::path;
+
+use gitbutler_core::{projects::ProjectId, sessions::SessionId};
+
+/// Events that flow through the [`super::Watcher`]'s internal channel.
+///
+/// Dispatchers turn filesystem activity into events on this channel, and
+/// [`super::handlers::Handler`] may emit follow-up events that are fed
+/// back onto it. The handler turns `SessionFlush`, `PushGitbutlerData`
+/// and `FetchGitbutlerData` into a `Notify` (when the project has
+/// notification targets) and delivers `Notify`; it does no work for
+/// `FileChange`, `GitFileChange` or `Fetch`.
+#[derive(Debug, Clone)]
+pub enum Event {
+    FileChange(path::PathBuf),
+    GitFileChange(ProjectId, path::PathBuf),
+    SessionFlush(ProjectId, SessionId),
+    PushGitbutlerData(ProjectId, String),
+    FetchGitbutlerData(ProjectId, String),
+    Fetch(ProjectId),
+    /// Emitted once something worth telling the user about has happened;
+    /// picked up by the notification handler, which delivers it to
+    /// whichever targets the project has configured.
+    Notify(Notification),
+}
+
+/// A short, already-rendered summary of watcher activity, ready to be
+/// delivered to a project's configured notification targets.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub project_id: ProjectId,
+    pub reference: Option<String>,
+    pub commit_id: Option<String>,
+    pub summary: String,
+}
@@ -0,0 +1,72 @@
+mod notification;
+
+pub use notification::NotificationHandler;
+
+use anyhow::Result;
+
+use crate::{bookmarks, deltas, files, gb_repository, projects, search, sessions};
+
+use super::events::Event;
+
+/// Turns a raw [`Event`] into whatever follow-up work it implies,
+/// returning any further events that should be fed back onto the
+/// watcher's loop (see [`super::Watcher::start`]).
+#[allow(clippy::too_many_arguments)]
+pub struct Handler<'handler> {
+    project_id: String,
+    project_store: projects::Storage,
+    gb_repository: &'handler gb_repository::Repository,
+    deltas_searcher: search::Searcher,
+    events_sender: crate::events::Sender,
+    sessions_database: sessions::Database,
+    deltas_database: deltas::Database,
+    files_database: files::Database,
+    bookmarks_database: bookmarks::Database,
+    notifier: NotificationHandler,
+}
+
+impl<'handler> Handler<'handler> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        project_id: String,
+        project_store: projects::Storage,
+        gb_repository: &'handler gb_repository::Repository,
+        deltas_searcher: search::Searcher,
+        events_sender: crate::events::Sender,
+        sessions_database: sessions::Database,
+        deltas_database: deltas::Database,
+        files_database: files::Database,
+        bookmarks_database: bookmarks::Database,
+    ) -> Self {
+        Self {
+            notifier: NotificationHandler::new(project_store.clone()),
+            project_id,
+            project_store,
+            gb_repository,
+            deltas_searcher,
+            events_sender,
+            sessions_database,
+            deltas_database,
+            files_database,
+            bookmarks_database,
+        }
+    }
+
+    pub fn handle(&self, event: Event) -> Result<Vec<Event>> {
+        match &event {
+            Event::SessionFlush(project_id, _)
+            | Event::PushGitbutlerData(project_id, _)
+            | Event::FetchGitbutlerData(project_id, _) => {
+                if let Some(notification) = self.notifier.summarize(project_id, &event)? {
+                    return Ok(vec![Event::Notify(notification)]);
+                }
+                Ok(vec![])
+            }
+            Event::Notify(notification) => {
+                self.notifier.deliver(notification.clone());
+                Ok(vec![])
+            }
+            Event::FileChange(_) | Event::GitFileChange(_, _) | Event::Fetch(_) => Ok(vec![]),
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use anyhow::{Context, Result};
+use gitbutler_core::projects::{self, ProjectId};
+
+use crate::watcher::events::{Event, Notification};
+
+/// Per-project delivery targets for watcher activity notifications. Either
+/// or both may be configured; an empty config means nothing is delivered.
+#[derive(Debug, Clone, Default)]
+pub struct NotificationTargets {
+    pub email_recipients: Vec<String>,
+    pub webhook_url: Option<String>,
+}
+
+/// Renders watcher activity into a short summary and delivers it to a
+/// project's configured targets. Delivery is best-effort: a failure is
+/// logged and retried a few times, but never stops the watcher's loop.
+#[derive(Clone)]
+pub struct NotificationHandler {
+    project_store: projects::Storage,
+}
+
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+
+impl NotificationHandler {
+    pub fn new(project_store: projects::Storage) -> Self {
+        Self { project_store }
+    }
+
+    /// Builds a [`Notification`] for `event`, if the project has any
+    /// delivery targets configured. Returns `Ok(None)` when there's
+    /// nothing to deliver to, so the caller can skip emitting a follow-up
+    /// event entirely.
+    pub fn summarize(&self, project_id: &ProjectId, event: &Event) -> Result<Option<Notification>> {
+        let project = self
+            .project_store
+            .get(project_id)
+            .context("failed to get project")?;
+        if self.targets(&project).is_none() {
+            return Ok(None);
+        }
+
+        let (reference, commit_id, summary) = match event {
+            Event::SessionFlush(_, session_id) => (
+                None,
+                Some(session_id.to_string()),
+                format!("{}: session {} flushed", project.title, session_id),
+            ),
+            Event::PushGitbutlerData(_, reference) => (
+                Some(reference.clone()),
+                None,
+                format!("{}: pushed {}", project.title, reference),
+            ),
+            Event::FetchGitbutlerData(_, reference) => (
+                Some(reference.clone()),
+                None,
+                format!("{}: fetched {}", project.title, reference),
+            ),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(Notification {
+            project_id: *project_id,
+            reference,
+            commit_id,
+            summary,
+        }))
+    }
+
+    /// Delivers `notification` to every configured target, retrying each
+    /// target independently on failure. Never returns an error: failures
+    /// are logged so a flaky webhook doesn't take down the watcher.
+    pub fn deliver(&self, notification: Notification) {
+        let Ok(project) = self.project_store.get(&notification.project_id) else {
+            log::error!(
+                "{}: failed to load project for notification delivery",
+                notification.project_id
+            );
+            return;
+        };
+        let Some(targets) = self.targets(&project) else {
+            return;
+        };
+
+        tauri::async_runtime::spawn(async move {
+            if let Some(webhook_url) = &targets.webhook_url {
+                if let Err(e) = Self::deliver_webhook(webhook_url, &notification).await {
+                    log::error!(
+                        "{}: failed to deliver webhook notification: {:#}",
+                        notification.project_id,
+                        e
+                    );
+                }
+            }
+            if !targets.email_recipients.is_empty() {
+                if let Err(e) =
+                    Self::deliver_email(&targets.email_recipients, &notification).await
+                {
+                    log::error!(
+                        "{}: failed to deliver email notification: {:#}",
+                        notification.project_id,
+                        e
+                    );
+                }
+            }
+        });
+    }
+
+    /// Reads delivery targets from the project's own git config, mirroring
+    /// how the gitoxide fast-path flag is configured: `gitbutler.notify.webhookUrl`
+    /// and a comma-separated `gitbutler.notify.emailRecipients`.
+    fn targets(&self, project: &projects::Project) -> Option<NotificationTargets> {
+        let config = git2::Repository::open(&project.path).ok()?.config().ok()?;
+        let webhook_url = config.get_string("gitbutler.notify.webhookUrl").ok();
+        let email_recipients = config
+            .get_string("gitbutler.notify.emailRecipients")
+            .ok()
+            .map(|recipients| {
+                recipients
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|s| !s.is_empty())
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+
+        if webhook_url.is_none() && email_recipients.is_empty() {
+            return None;
+        }
+        Some(NotificationTargets {
+            email_recipients,
+            webhook_url,
+        })
+    }
+
+    async fn deliver_webhook(url: &str, notification: &Notification) -> Result<()> {
+        let client = reqwest::Client::new();
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let result = client
+                .post(url)
+                .json(&serde_json::json!({
+                    "project_id": notification.project_id,
+                    "reference": notification.reference,
+                    "commit_id": notification.commit_id,
+                    "summary": notification.summary,
+                }))
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status);
+            match result {
+                Ok(_) => return Ok(()),
+                Err(e) if attempt < MAX_DELIVERY_ATTEMPTS => {
+                    log::warn!(
+                        "{}: webhook delivery attempt {} failed, retrying: {:#}",
+                        notification.project_id,
+                        attempt,
+                        e
+                    );
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Delivers to each recipient independently: a malformed address or a
+    /// permanently failing mailbox is logged and skipped rather than
+    /// aborting delivery to every other configured recipient.
+    async fn deliver_email(recipients: &[String], notification: &Notification) -> Result<()> {
+        use lettre::{AsyncTransport, Message};
+
+        let mailer = lettre::AsyncSmtpTransport::<lettre::Tokio1Executor>::unencrypted_localhost();
+        for recipient in recipients {
+            let email = match Message::builder()
+                .from("gitbutler@localhost".parse()?)
+                .to(match recipient.parse() {
+                    Ok(address) => address,
+                    Err(e) => {
+                        log::error!(
+                            "{}: invalid notification recipient {}: {:#}",
+                            notification.project_id,
+                            recipient,
+                            e
+                        );
+                        continue;
+                    }
+                })
+                .subject("GitButler activity")
+                .body(notification.summary.clone())
+            {
+                Ok(email) => email,
+                Err(e) => {
+                    log::error!(
+                        "{}: failed to build notification email for {}: {:#}",
+                        notification.project_id,
+                        recipient,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            let mut attempt = 0;
+            loop {
+                attempt += 1;
+                match mailer.send(email.clone()).await {
+                    Ok(_) => break,
+                    Err(e) if attempt < MAX_DELIVERY_ATTEMPTS => {
+                        log::warn!(
+                            "{}: email delivery attempt {} to {} failed, retrying: {:#}",
+                            notification.project_id,
+                            attempt,
+                            recipient,
+                            e
+                        );
+                    }
+                    Err(e) => {
+                        log::error!(
+                            "{}: email delivery to {} failed after {} attempts: {:#}",
+                            notification.project_id,
+                            recipient,
+                            attempt,
+                            e
+                        );
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Drops webhook deliveries that repeat a `ref`+sha pair we've already
+/// processed recently. Forges commonly redeliver the same push event
+/// (retries, multiple configured endpoints, ...); without this a single
+/// push could trigger a fetch per delivery.
+pub struct Dedupe {
+    window: Duration,
+    seen: HashMap<(String, String), Instant>,
+}
+
+impl Dedupe {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Returns `true` the first time `(reference, sha)` is seen within the
+    /// dedupe window, and `false` for any repeat within that window.
+    pub fn check_and_record(&mut self, reference: &str, sha: &str, now: Instant) -> bool {
+        self.seen.retain(|_, seen_at| now.duration_since(*seen_at) < self.window);
+
+        let key = (reference.to_string(), sha.to_string());
+        if self.seen.contains_key(&key) {
+            return false;
+        }
+        self.seen.insert(key, now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_delivery_is_new() {
+        let mut dedupe = Dedupe::new(Duration::from_secs(30));
+        assert!(dedupe.check_and_record("refs/heads/main", "sha1", Instant::now()));
+    }
+
+    #[test]
+    fn duplicate_within_window_is_rejected() {
+        let mut dedupe = Dedupe::new(Duration::from_secs(30));
+        let now = Instant::now();
+        assert!(dedupe.check_and_record("refs/heads/main", "sha1", now));
+        assert!(!dedupe.check_and_record("refs/heads/main", "sha1", now + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn duplicate_after_window_is_accepted_again() {
+        let mut dedupe = Dedupe::new(Duration::from_secs(30));
+        let now = Instant::now();
+        assert!(dedupe.check_and_record("refs/heads/main", "sha1", now));
+        assert!(dedupe.check_and_record(
+            "refs/heads/main",
+            "sha1",
+            now + Duration::from_secs(31)
+        ));
+    }
+
+    #[test]
+    fn different_sha_for_same_ref_is_not_a_duplicate() {
+        let mut dedupe = Dedupe::new(Duration::from_secs(30));
+        let now = Instant::now();
+        assert!(dedupe.check_and_record("refs/heads/main", "sha1", now));
+        assert!(dedupe.check_and_record("refs/heads/main", "sha2", now));
+    }
+}
@@ -0,0 +1,329 @@
+mod dedupe;
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Context, Result};
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Router,
+};
+use gitbutler_core::projects::{self, ProjectId};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::watcher;
+use dedupe::Dedupe;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// How long a `ref`+sha delivery is remembered for deduplication.
+const DEDUPE_WINDOW: Duration = Duration::from_secs(30);
+
+const SIGNATURE_HEADER: &str = "x-hub-signature-256";
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebhookError {
+    #[error("unknown project")]
+    UnknownProject,
+    #[error("missing signature header")]
+    MissingSignature,
+    #[error("signature does not match payload")]
+    InvalidSignature,
+    #[error("project has no webhook secret configured")]
+    NoSecretConfigured,
+    #[error("malformed payload: {0}")]
+    MalformedPayload(String),
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+#[derive(serde::Deserialize)]
+struct PushPayload {
+    #[serde(rename = "ref")]
+    reference: String,
+    after: String,
+    repository: RepositoryPayload,
+}
+
+#[derive(serde::Deserialize)]
+struct RepositoryPayload {
+    full_name: String,
+}
+
+/// Receives signed forge "push" webhooks and, for a project's tracked
+/// remote, injects a [`watcher::Event::Fetch`] into that project's watcher.
+/// The watcher's handler does no work for `Event::Fetch`, so a delivery
+/// does not by itself fetch from the remote or refresh remote branches.
+///
+/// Every payload must carry a valid HMAC-SHA256 signature over the raw
+/// request body, keyed by a per-project shared secret configured via
+/// `gitbutler.webhook.secret` in the project's git config (mirroring how
+/// [`crate::watcher::handlers::NotificationHandler`] reads its delivery
+/// targets). An unverified or unsigned payload is never acted on.
+#[derive(Clone)]
+pub struct WebhookListener {
+    projects: projects::Controller,
+    senders: Arc<Mutex<HashMap<ProjectId, crossbeam_channel::Sender<watcher::Event>>>>,
+    dedupe: Arc<Mutex<Dedupe>>,
+}
+
+impl WebhookListener {
+    pub fn new(projects: projects::Controller) -> Self {
+        Self {
+            projects,
+            senders: Arc::new(Mutex::new(HashMap::new())),
+            dedupe: Arc::new(Mutex::new(Dedupe::new(DEDUPE_WINDOW))),
+        }
+    }
+
+    /// Registers the channel a running [`watcher::Watcher`] for `project_id`
+    /// listens on, so webhook deliveries for that project can reach it.
+    pub fn register(&self, project_id: ProjectId, sender: crossbeam_channel::Sender<watcher::Event>) {
+        self.senders
+            .lock()
+            .unwrap()
+            .insert(project_id, sender);
+    }
+
+    pub fn unregister(&self, project_id: &ProjectId) {
+        self.senders.lock().unwrap().remove(project_id);
+    }
+
+    pub fn router(self: Arc<Self>) -> Router {
+        Router::new()
+            .route("/webhooks/:project_id", post(handle_push))
+            .with_state(self)
+    }
+
+    fn secret(&self, project: &projects::Project) -> Result<String, WebhookError> {
+        let config = git2::Repository::open(&project.path)
+            .context("failed to open project repository")?
+            .config()
+            .context("failed to open git config")?;
+        config
+            .get_string("gitbutler.webhook.secret")
+            .map_err(|_| WebhookError::NoSecretConfigured)
+    }
+
+    fn verify(&self, project: &projects::Project, body: &[u8], signature: &str) -> Result<(), WebhookError> {
+        let secret = self.secret(project)?;
+        verify_signature(&secret, body, signature)
+    }
+
+    fn handle_payload(&self, project_id: ProjectId, body: &[u8], signature: Option<&str>) -> Result<(), WebhookError> {
+        let project = self
+            .projects
+            .get(&project_id)
+            .map_err(|_| WebhookError::UnknownProject)?;
+
+        let signature = signature.ok_or(WebhookError::MissingSignature)?;
+        self.verify(&project, body, signature)?;
+
+        let payload: PushPayload = serde_json::from_slice(body)
+            .map_err(|e| WebhookError::MalformedPayload(e.to_string()))?;
+
+        // Only react to pushes against a remote this project actually
+        // tracks: one of its configured remotes' URLs must name the pushed
+        // repository. This matching is done on the remote URLs here and
+        // does not go through `App::git_remote_branches`.
+        let tracks_remote = project_repository_tracks(&project, &payload.repository.full_name)?;
+        if !tracks_remote {
+            return Ok(());
+        }
+
+        let is_new = self.dedupe.lock().unwrap().check_and_record(
+            &payload.reference,
+            &payload.after,
+            Instant::now(),
+        );
+        if !is_new {
+            return Ok(());
+        }
+
+        let Some(sender) = self.senders.lock().unwrap().get(&project_id).cloned() else {
+            return Ok(());
+        };
+        sender
+            .send(watcher::Event::Fetch(project_id))
+            .map_err(|e| WebhookError::Other(anyhow!(e)))
+    }
+}
+
+/// Verifies `body` against the hex-encoded (optionally `sha256=`-prefixed)
+/// HMAC-SHA256 `signature`, computed with `secret`. The comparison is
+/// constant-time (`Mac::verify_slice` already is), so a mismatch can't be
+/// distinguished by timing.
+fn verify_signature(secret: &str, body: &[u8], signature: &str) -> Result<(), WebhookError> {
+    let signature = signature.strip_prefix("sha256=").unwrap_or(signature);
+    let signature = hex::decode(signature).map_err(|_| WebhookError::InvalidSignature)?;
+
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .map_err(|e| WebhookError::Other(anyhow!(e)))?;
+    mac.update(body);
+    mac.verify_slice(&signature)
+        .map_err(|_| WebhookError::InvalidSignature)
+}
+
+/// Checks whether any of the project's configured remotes point at
+/// `full_name` (e.g. `owner/repo`), the same remote-matching the webhook
+/// payload needs before triggering a fetch.
+fn project_repository_tracks(project: &projects::Project, full_name: &str) -> Result<bool, WebhookError> {
+    let repo = git2::Repository::open(&project.path)
+        .map_err(|e| WebhookError::Other(anyhow!(e)))?;
+    let remotes = repo
+        .remotes()
+        .map_err(|e| WebhookError::Other(anyhow!(e)))?;
+    for name in remotes.iter().flatten() {
+        let Ok(remote) = repo.find_remote(name) else {
+            continue;
+        };
+        if let Some(url) = remote.url() {
+            if remote_full_name(url).is_some_and(|remote_full_name| {
+                remote_full_name.eq_ignore_ascii_case(full_name)
+            }) {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
+
+/// Extracts the `owner/repo` component from a remote URL, supporting the
+/// usual forms (`https://host/owner/repo(.git)`, `ssh://git@host/owner/repo`,
+/// scp-like `git@host:owner/repo.git`). The whole URL is split on `/` and
+/// `:` and the last two non-empty segments are taken as owner and repo, so
+/// a URL with a single path segment yields `host/repo`. Returns `None` only
+/// if there are fewer than two segments.
+///
+/// Matching is anchored to these two trailing segments rather than done as
+/// a raw substring search, so a crafted `owner/repo` value (e.g.
+/// `evil-org/evil/repo-name`) can't be made to match an unrelated remote
+/// URL that merely happens to contain the same characters somewhere.
+fn remote_full_name(url: &str) -> Option<String> {
+    let trimmed = url.trim_end_matches('/');
+    let trimmed = trimmed.strip_suffix(".git").unwrap_or(trimmed);
+    let segments: Vec<&str> = trimmed
+        .split(['/', ':'])
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let repo = *segments.last()?;
+    let owner = *segments.get(segments.len().checked_sub(2)?)?;
+    Some(format!("{owner}/{repo}"))
+}
+
+async fn handle_push(
+    State(listener): State<Arc<WebhookListener>>,
+    Path(project_id): Path<ProjectId>,
+    headers: HeaderMap,
+    body: axum::body::Bytes,
+) -> StatusCode {
+    let signature = headers
+        .get(SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok());
+
+    match listener.handle_payload(project_id, &body, signature) {
+        Ok(()) => StatusCode::NO_CONTENT,
+        Err(WebhookError::UnknownProject) => StatusCode::NOT_FOUND,
+        Err(WebhookError::MissingSignature | WebhookError::InvalidSignature) => {
+            StatusCode::UNAUTHORIZED
+        }
+        Err(WebhookError::NoSecretConfigured) => StatusCode::PRECONDITION_FAILED,
+        Err(WebhookError::MalformedPayload(_)) => StatusCode::BAD_REQUEST,
+        Err(WebhookError::Other(e)) => {
+            log::error!("{}: failed to handle webhook delivery: {:#}", project_id, e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sign(secret: &str, body: &[u8]) -> String {
+        let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).unwrap();
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    #[test]
+    fn verify_signature_accepts_matching_signature() {
+        let body = b"{\"ref\":\"refs/heads/main\"}";
+        let signature = sign("shh", body);
+        assert!(verify_signature("shh", body, &signature).is_ok());
+    }
+
+    #[test]
+    fn verify_signature_accepts_signature_without_prefix() {
+        let body = b"{\"ref\":\"refs/heads/main\"}";
+        let signature = sign("shh", body);
+        let signature = signature.strip_prefix("sha256=").unwrap();
+        assert!(verify_signature("shh", body, signature).is_ok());
+    }
+
+    #[test]
+    fn verify_signature_rejects_wrong_secret() {
+        let body = b"{\"ref\":\"refs/heads/main\"}";
+        let signature = sign("shh", body);
+        assert!(matches!(
+            verify_signature("wrong", body, &signature),
+            Err(WebhookError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn verify_signature_rejects_tampered_body() {
+        let signature = sign("shh", b"original");
+        assert!(matches!(
+            verify_signature("shh", b"tampered", &signature),
+            Err(WebhookError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn verify_signature_rejects_malformed_hex() {
+        assert!(matches!(
+            verify_signature("shh", b"body", "sha256=not-hex"),
+            Err(WebhookError::InvalidSignature)
+        ));
+    }
+
+    #[test]
+    fn remote_full_name_handles_https_url() {
+        assert_eq!(
+            remote_full_name("https://github.com/owner/repo.git"),
+            Some("owner/repo".to_string())
+        );
+    }
+
+    #[test]
+    fn remote_full_name_handles_scp_like_url() {
+        assert_eq!(
+            remote_full_name("git@github.com:owner/repo.git"),
+            Some("owner/repo".to_string())
+        );
+    }
+
+    #[test]
+    fn remote_full_name_handles_ssh_url() {
+        assert_eq!(
+            remote_full_name("ssh://git@github.com/owner/repo"),
+            Some("owner/repo".to_string())
+        );
+    }
+
+    #[test]
+    fn remote_full_name_does_not_substring_match() {
+        // A crafted `full_name` sharing characters with the URL must not
+        // be treated as a match just because it's a substring somewhere.
+        let tracked = remote_full_name("https://github.com/org/repo.git").unwrap();
+        assert_ne!(tracked, "evil-org/evil/repo-name");
+        assert!(!tracked.eq_ignore_ascii_case("evil-org/evil/repo-name"));
+    }
+}